@@ -1,9 +1,18 @@
+use crate::completions;
+use crate::flags::{self, FlagKind};
+use crate::help;
+use crate::suggest;
+use crate::tokens::{Token, Tokenizer};
+
 #[derive(Clone)]
 pub struct Args {
     pub message: String,
     pub buttons: Vec<ArgButton>,
     pub message_type: String,
     pub detailed_message: bool,
+    pub icon: Option<String>,
+    pub copy_button: Option<String>,
+    pub timeout: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -12,42 +21,106 @@ pub struct ArgButton {
     pub action: String,
 }
 
-pub fn parse(args: impl Iterator<Item = String>) -> Result<Args, String> {
+/// The result of [`parse`]ing the command line.
+pub enum Parsed {
+    Args(Args),
+    /// `-h/--help`, `--version`, or `--completions` already printed their output;
+    /// the caller should exit 0 rather than treat this as an error.
+    Handled,
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<Parsed, String> {
     let mut message = None;
     let mut message_type = None;
     let mut buttons = vec![];
     let mut detailed_message = false;
+    let mut icon = None;
+    let mut copy_button = None;
+    let mut timeout = None;
+    let mut literal_message_words = vec![];
 
     // skip the binary name
-    let mut args = args.skip(1);
+    let mut tokens = Tokenizer::new(args.skip(1));
 
-    loop {
-        match args.next().as_deref() {
-            Some("-m") | Some("--message") => {
-                let message_arg = args.next();
+    while let Some(token) = tokens.next_token() {
+        let (alias, attached) = match token {
+            Token::Flag { alias, attached } => (alias, attached),
+            // Everything after a `--` terminator is literal text, used as the
+            // message when one wasn't given via -m/--message.
+            Token::Literal(word) => {
+                literal_message_words.push(word);
+                continue;
+            }
+        };
 
-                if message_arg.is_some() {
-                    message = message_arg;
-                } else {
-                    return Err("missing required arg message (-m/--message)".into());
-                }
+        let flag = match flags::lookup(&alias) {
+            Some(flag) => flag,
+            None => {
+                return Err(match suggest::closest_flag(&alias) {
+                    Some(suggestion) => {
+                        format!("invalid arg '{}', did you mean '{}'?", alias, suggestion)
+                    }
+                    None => format!("invalid arg '{}'", alias),
+                })
+            }
+        };
+
+        if flag.value_hint.is_none() && attached.is_some() {
+            return Err(format!("'{}' does not take a value", alias));
+        }
+
+        match flag.kind {
+            FlagKind::Help => {
+                print!("{}", help::render());
+                return Ok(Parsed::Handled);
             }
-            Some("-t") | Some("--type") => {
-                let message_type_arg = args.next();
+            FlagKind::Version => {
+                println!("waysay {}", env!("CARGO_PKG_VERSION"));
+                return Ok(Parsed::Handled);
+            }
+            FlagKind::Completions => {
+                let shell = tokens.value(attached);
 
-                if message_type_arg.is_some() {
-                    message_type = message_type_arg;
-                } else {
-                    return Err("missing required arg type (-t/--type)".into());
+                match shell.as_deref().map(completions::generate) {
+                    Some(Ok(script)) => {
+                        print!("{}", script);
+                        return Ok(Parsed::Handled);
+                    }
+                    Some(Err(e)) => return Err(e),
+                    None => return Err("missing required arg shell (--completions)".into()),
                 }
             }
-            Some("-l") | Some("--detailed-message") => {
-                detailed_message = true;
+            FlagKind::Message => {
+                message = Some(require_value(
+                    tokens.value(attached),
+                    "message (-m/--message)",
+                )?);
+            }
+            FlagKind::Type => {
+                message_type = Some(require_value(tokens.value(attached), "type (-t/--type)")?);
+            }
+            FlagKind::DetailedMessage => detailed_message = true,
+            FlagKind::Icon => {
+                icon = Some(require_value(tokens.value(attached), "path (--icon)")?)
+            }
+            FlagKind::CopyButton => {
+                copy_button = Some(require_value(
+                    tokens.value(attached),
+                    "text (--copy-button)",
+                )?)
+            }
+            FlagKind::Timeout => {
+                let value = require_value(tokens.value(attached), "seconds (--timeout)")?;
+                timeout = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid value '{}' for seconds (--timeout)", value))?,
+                );
             }
             // For now handle both -b and -B the same
-            Some("-b") | Some("--button") | Some("-B") | Some("--button-no-terminal") => {
-                let text = args.next();
-                let action = args.next();
+            FlagKind::Button | FlagKind::ButtonNoTerminal => {
+                let text = tokens.value(attached);
+                let action = tokens.value(None);
 
                 match (text, action) {
                     (Some(text), Some(action)) => buttons.push(ArgButton { text, action }),
@@ -55,26 +128,45 @@ pub fn parse(args: impl Iterator<Item = String>) -> Result<Args, String> {
                     (Some(_), None) => return Err("button missing action".into()),
                 }
             }
-            Some(arg) => return Err(format!("invalid arg '{}'", arg)),
-            None => break,
         }
     }
 
+    let message = match (message, literal_message_words.is_empty()) {
+        (Some(_), false) => return Err("unexpected arguments after '--'".into()),
+        (Some(message), true) => Some(message),
+        (None, false) => Some(literal_message_words.join(" ")),
+        (None, true) => None,
+    };
+
     if let Some(message) = message {
-        Ok(Args {
+        Ok(Parsed::Args(Args {
             message,
             buttons,
             message_type: message_type.unwrap_or_else(|| "error".into()),
             detailed_message,
-        })
+            icon,
+            copy_button,
+            timeout,
+        }))
     } else {
         Err("missing required arg message (-m/--message)".into())
     }
 }
 
+fn require_value(value: Option<String>, name: &str) -> Result<String, String> {
+    value.ok_or_else(|| format!("missing required arg {}", name))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse;
+    use super::{parse, Parsed};
+
+    fn parse_args(input: Vec<String>) -> Result<super::Args, String> {
+        match parse(input.into_iter())? {
+            Parsed::Args(args) => Ok(args),
+            Parsed::Handled => panic!("expected Args, got Handled"),
+        }
+    }
 
     #[test]
     fn no_args() {
@@ -82,7 +174,7 @@ mod tests {
 
         assert_eq!(
             "missing required arg message (-m/--message)",
-            parse(input.into_iter()).err().unwrap(),
+            parse_args(input).err().unwrap(),
         );
     }
 
@@ -92,7 +184,7 @@ mod tests {
 
         assert_eq!(
             "invalid arg '--not-a-real-thing'",
-            parse(input.into_iter()).err().unwrap(),
+            parse_args(input).err().unwrap(),
         );
     }
 
@@ -100,7 +192,7 @@ mod tests {
     fn message_short_flag() {
         let input = vec!["waysay".into(), "-m".into(), "hello from waysay".into()];
 
-        let args = parse(input.into_iter()).unwrap();
+        let args = parse_args(input).unwrap();
         assert_eq!("hello from waysay", args.message,);
     }
 
@@ -112,7 +204,54 @@ mod tests {
             "hello from waysay".into(),
         ];
 
-        let args = parse(input.into_iter()).unwrap();
+        let args = parse_args(input).unwrap();
         assert_eq!("hello from waysay", args.message,);
     }
+
+    #[test]
+    fn message_long_flag_with_equals() {
+        let input = vec!["waysay".into(), "--message=hello from waysay".into()];
+
+        let args = parse_args(input).unwrap();
+        assert_eq!("hello from waysay", args.message,);
+    }
+
+    #[test]
+    fn message_short_flag_bundled() {
+        let input = vec!["waysay".into(), "-mhello".into()];
+
+        let args = parse_args(input).unwrap();
+        assert_eq!("hello", args.message,);
+    }
+
+    #[test]
+    fn message_after_terminator() {
+        let input = vec!["waysay".into(), "--".into(), "-not-a-flag".into()];
+
+        let args = parse_args(input).unwrap();
+        assert_eq!("-not-a-flag", args.message,);
+    }
+
+    fn assert_handled(input: Vec<String>) {
+        match parse(input.into_iter()) {
+            Ok(Parsed::Handled) => {}
+            Ok(Parsed::Args(_)) => panic!("expected Handled, got Args"),
+            Err(e) => panic!("expected Handled, got Err({})", e),
+        }
+    }
+
+    #[test]
+    fn help_short_flag_is_handled() {
+        assert_handled(vec!["waysay".into(), "-h".into()]);
+    }
+
+    #[test]
+    fn help_long_flag_is_handled() {
+        assert_handled(vec!["waysay".into(), "--help".into()]);
+    }
+
+    #[test]
+    fn version_flag_is_handled() {
+        assert_handled(vec!["waysay".into(), "--version".into()]);
+    }
 }
@@ -0,0 +1,177 @@
+//! Static shell completion scripts for `waysay`'s flags.
+//!
+//! Each generator walks [`flags::FLAGS`](crate::flags::FLAGS), the same table
+//! `args::parse` dispatches on, and prints the shell-specific directives for it. The
+//! scripts are static (no dynamic value completion) and meant to be sourced, e.g.
+//! `waysay --completions zsh > _waysay`.
+
+use crate::flags::FLAGS;
+
+/// Generates a completion script for `shell`, one of `bash`, `zsh`, `fish` or `elvish`.
+pub fn generate(shell: &str) -> Result<String, String> {
+    match shell {
+        "bash" => Ok(bash()),
+        "zsh" => Ok(zsh()),
+        "fish" => Ok(fish()),
+        "elvish" => Ok(elvish()),
+        _ => Err(format!(
+            "unsupported shell '{}' (--completions bash|zsh|fish|elvish)",
+            shell
+        )),
+    }
+}
+
+fn bash() -> String {
+    let mut flags = String::new();
+    for flag in FLAGS {
+        flags.push_str(flag.long());
+        flags.push(' ');
+        if let Some(short) = flag.short() {
+            flags.push_str(short);
+            flags.push(' ');
+        }
+    }
+    let flags = flags.trim_end();
+
+    format!(
+        "_waysay() {{\n    COMPREPLY=($(compgen -W \"{}\" -- \"${{COMP_WORDS[COMP_CWORD]}}\"))\n}}\ncomplete -F _waysay waysay\n",
+        flags,
+    )
+}
+
+fn zsh() -> String {
+    let mut arguments = String::new();
+    for flag in FLAGS {
+        let names = match flag.short() {
+            Some(short) => format!("{{{},{}}}", short, flag.long()),
+            None => flag.long().to_string(),
+        };
+        let value = if flag.value_hint.is_some() {
+            ":value:"
+        } else {
+            ""
+        };
+        arguments.push_str(&format!("    '{}[{}]{}'\\\n", names, flag.help, value));
+    }
+
+    format!(
+        "#compdef waysay\n\n_arguments \\\n{}\n",
+        arguments.trim_end_matches("\\\n")
+    )
+}
+
+fn fish() -> String {
+    let mut script = String::new();
+    for flag in FLAGS {
+        script.push_str("complete -c waysay");
+        if let Some(short) = flag.short() {
+            script.push_str(&format!(" -s {}", short.trim_start_matches('-')));
+        }
+        script.push_str(&format!(" -l {}", flag.long().trim_start_matches("--")));
+        if flag.value_hint.is_some() {
+            script.push_str(" -r");
+        }
+        script.push_str(&format!(" -d '{}'\n", flag.help));
+    }
+    script
+}
+
+fn elvish() -> String {
+    let mut flags = String::new();
+    for flag in FLAGS {
+        flags.push_str(&format!("        '{}'\n", flag.long()));
+        if let Some(short) = flag.short() {
+            flags.push_str(&format!("        '{}'\n", short));
+        }
+    }
+
+    format!(
+        "set edit:completion:arg-completer[waysay] = {{|@args|\n    put {{\n{}    }}\n}}\n",
+        flags,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::generate;
+
+    #[test]
+    fn unsupported_shell() {
+        assert_eq!(
+            "unsupported shell 'powershell' (--completions bash|zsh|fish|elvish)",
+            generate("powershell").err().unwrap(),
+        );
+    }
+
+    #[test]
+    fn bash() {
+        let script = generate("bash").unwrap();
+
+        assert_eq!(
+            "_waysay() {\n    COMPREPLY=($(compgen -W \"--message -m --type -t --detailed-message -l --icon --copy-button --timeout --button -b --button-no-terminal -B --completions --help -h --version\" -- \"${COMP_WORDS[COMP_CWORD]}\"))\n}\ncomplete -F _waysay waysay\n",
+            script,
+        );
+    }
+
+    #[test]
+    fn zsh() {
+        let script = generate("zsh").unwrap();
+
+        assert_eq!(
+            "#compdef waysay\n\n_arguments \\\n    \
+             '{-m,--message}[Message to display]:value:'\\\n    \
+             '{-t,--type}[Message type (e.g. error, warn)]:value:'\\\n    \
+             '{-l,--detailed-message}[Read an additional detailed message from stdin]'\\\n    \
+             '--icon[Path to an image to render beside the message]:value:'\\\n    \
+             '--copy-button[Add a button that copies the message to the clipboard]:value:'\\\n    \
+             '--timeout[Seconds before the message auto-dismisses]:value:'\\\n    \
+             '{-b,--button}[Add a button that runs a command]:value:'\\\n    \
+             '{-B,--button-no-terminal}[Add a button that runs a command]:value:'\\\n    \
+             '--completions[Print a shell completion script (bash, zsh, fish, elvish)]:value:'\\\n    \
+             '{-h,--help}[Print this help text]'\\\n    \
+             '--version[Print version information]'\n",
+            script,
+        );
+    }
+
+    #[test]
+    fn fish() {
+        let script = generate("fish").unwrap();
+
+        assert_eq!(
+            "complete -c waysay -s m -l message -r -d 'Message to display'\n\
+             complete -c waysay -s t -l type -r -d 'Message type (e.g. error, warn)'\n\
+             complete -c waysay -s l -l detailed-message -d 'Read an additional detailed message from stdin'\n\
+             complete -c waysay -l icon -r -d 'Path to an image to render beside the message'\n\
+             complete -c waysay -l copy-button -r -d 'Add a button that copies the message to the clipboard'\n\
+             complete -c waysay -l timeout -r -d 'Seconds before the message auto-dismisses'\n\
+             complete -c waysay -s b -l button -r -d 'Add a button that runs a command'\n\
+             complete -c waysay -s B -l button-no-terminal -r -d 'Add a button that runs a command'\n\
+             complete -c waysay -l completions -r -d 'Print a shell completion script (bash, zsh, fish, elvish)'\n\
+             complete -c waysay -s h -l help -d 'Print this help text'\n\
+             complete -c waysay -l version -d 'Print version information'\n",
+            script,
+        );
+    }
+
+    #[test]
+    fn elvish() {
+        let script = generate("elvish").unwrap();
+
+        assert_eq!(
+            "set edit:completion:arg-completer[waysay] = {|@args|\n    put {\n        \
+             '--message'\n        '-m'\n        \
+             '--type'\n        '-t'\n        \
+             '--detailed-message'\n        '-l'\n        \
+             '--icon'\n        \
+             '--copy-button'\n        \
+             '--timeout'\n        \
+             '--button'\n        '-b'\n        \
+             '--button-no-terminal'\n        '-B'\n        \
+             '--completions'\n        \
+             '--help'\n        '-h'\n        \
+             '--version'\n    }\n}\n",
+            script,
+        );
+    }
+}
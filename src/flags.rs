@@ -0,0 +1,126 @@
+//! The declarative list of every `waysay` flag.
+//!
+//! [`args::parse`](crate::args::parse), [`help::render`](crate::help::render), and the
+//! [`completions`](crate::completions) generators all walk this same table, so adding or
+//! renaming a flag here updates parsing, `--help`, and shell completions at once.
+
+/// Which field of `Args` a flag maps to, used by the parser's dispatch.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum FlagKind {
+    Message,
+    Type,
+    DetailedMessage,
+    Icon,
+    CopyButton,
+    Timeout,
+    Button,
+    ButtonNoTerminal,
+    Completions,
+    Help,
+    Version,
+}
+
+pub struct Flag {
+    pub kind: FlagKind,
+    /// Short and/or long forms, e.g. `["-m", "--message"]`.
+    pub aliases: &'static [&'static str],
+    /// Placeholder(s) for the value(s) this flag takes, or `None` if it takes none.
+    pub value_hint: Option<&'static str>,
+    pub help: &'static str,
+}
+
+impl Flag {
+    /// The flag's short form, e.g. `-m`, if it has one.
+    pub fn short(&self) -> Option<&'static str> {
+        self.aliases.iter().find(|alias| !alias.starts_with("--")).copied()
+    }
+
+    /// The flag's long form, e.g. `--message`.
+    pub fn long(&self) -> &'static str {
+        self.aliases
+            .iter()
+            .find(|alias| alias.starts_with("--"))
+            .copied()
+            .unwrap_or(self.aliases[0])
+    }
+}
+
+pub const FLAGS: &[Flag] = &[
+    Flag {
+        kind: FlagKind::Message,
+        aliases: &["-m", "--message"],
+        value_hint: Some("<message>"),
+        help: "Message to display",
+    },
+    Flag {
+        kind: FlagKind::Type,
+        aliases: &["-t", "--type"],
+        value_hint: Some("<type>"),
+        help: "Message type (e.g. error, warn)",
+    },
+    Flag {
+        kind: FlagKind::DetailedMessage,
+        aliases: &["-l", "--detailed-message"],
+        value_hint: None,
+        help: "Read an additional detailed message from stdin",
+    },
+    Flag {
+        kind: FlagKind::Icon,
+        aliases: &["--icon"],
+        value_hint: Some("<path>"),
+        help: "Path to an image to render beside the message",
+    },
+    Flag {
+        kind: FlagKind::CopyButton,
+        aliases: &["--copy-button"],
+        value_hint: Some("<text>"),
+        help: "Add a button that copies the message to the clipboard",
+    },
+    Flag {
+        kind: FlagKind::Timeout,
+        aliases: &["--timeout"],
+        value_hint: Some("<seconds>"),
+        help: "Seconds before the message auto-dismisses",
+    },
+    // For now handle both -b and -B the same
+    Flag {
+        kind: FlagKind::Button,
+        aliases: &["-b", "--button"],
+        value_hint: Some("<text> <action>"),
+        help: "Add a button that runs a command",
+    },
+    Flag {
+        kind: FlagKind::ButtonNoTerminal,
+        aliases: &["-B", "--button-no-terminal"],
+        value_hint: Some("<text> <action>"),
+        help: "Add a button that runs a command",
+    },
+    Flag {
+        kind: FlagKind::Completions,
+        aliases: &["--completions"],
+        value_hint: Some("<shell>"),
+        help: "Print a shell completion script (bash, zsh, fish, elvish)",
+    },
+    Flag {
+        kind: FlagKind::Help,
+        aliases: &["-h", "--help"],
+        value_hint: None,
+        help: "Print this help text",
+    },
+    Flag {
+        kind: FlagKind::Version,
+        aliases: &["--version"],
+        value_hint: None,
+        help: "Print version information",
+    },
+];
+
+/// Finds the flag, if any, whose aliases contain `token`.
+pub fn lookup(token: &str) -> Option<&'static Flag> {
+    FLAGS.iter().find(|flag| flag.aliases.contains(&token))
+}
+
+/// The long form of every flag, e.g. `--message`, `--type`, ...
+pub fn long_flags() -> impl Iterator<Item = &'static str> {
+    FLAGS.iter().map(Flag::long)
+}
@@ -0,0 +1,50 @@
+//! Renders the `-h/--help` usage block from the shared [`flags::FLAGS`] table.
+
+use crate::flags::FLAGS;
+
+pub fn render() -> String {
+    let mut usage = String::from(
+        "waysay - display a message box on a Wayland compositor\n\nUSAGE:\n    waysay [FLAGS]\n\nFLAGS:\n",
+    );
+
+    for flag in FLAGS {
+        let mut names = flag.aliases.join(", ");
+        if let Some(value_hint) = flag.value_hint {
+            names.push(' ');
+            names.push_str(value_hint);
+        }
+
+        usage.push_str(&format!("    {:<28} {}\n", names, flag.help));
+    }
+
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn lists_every_flag() {
+        assert_eq!(
+            "waysay - display a message box on a Wayland compositor\n\
+             \n\
+             USAGE:\n    \
+             waysay [FLAGS]\n\
+             \n\
+             FLAGS:\n    \
+             -m, --message <message>      Message to display\n    \
+             -t, --type <type>            Message type (e.g. error, warn)\n    \
+             -l, --detailed-message       Read an additional detailed message from stdin\n    \
+             --icon <path>                Path to an image to render beside the message\n    \
+             --copy-button <text>         Add a button that copies the message to the clipboard\n    \
+             --timeout <seconds>          Seconds before the message auto-dismisses\n    \
+             -b, --button <text> <action> Add a button that runs a command\n    \
+             -B, --button-no-terminal <text> <action> Add a button that runs a command\n    \
+             --completions <shell>        Print a shell completion script (bash, zsh, fish, elvish)\n    \
+             -h, --help                   Print this help text\n    \
+             --version                    Print version information\n",
+            render(),
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Decoding and blitting of `--icon` images onto a `Surface`'s pixel buffer.
+//!
+//! `andrew::Canvas` only knows how to draw text and rectangles, so rendering an
+//! icon next to the message requires decoding the PNG ourselves and writing
+//! premultiplied, native-endian ARGB8888 pixels directly into the canvas buffer.
+
+use andrew::Endian;
+
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Premultiplied, native-endian ARGB8888 pixel data, `width * height * 4` bytes
+    argb: Vec<u8>,
+    /// Per-pixel alpha (0-255), parallel to `argb`, used to blend onto the background
+    alpha: Vec<u8>,
+}
+
+pub fn load(path: &str) -> Result<Image, String> {
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("failed to open icon '{}': {}", path, e))?;
+    let mut reader = png::Decoder::new(file)
+        .read_info()
+        .map_err(|e| format!("failed to read icon '{}': {}", path, e))?;
+
+    let mut raw = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut raw)
+        .map_err(|e| format!("failed to decode icon '{}': {}", path, e))?;
+    let raw = &raw[..info.buffer_size()];
+
+    // The chunking below assumes one byte per channel; reject anything else
+    // explicitly rather than silently misaligning the pixel data.
+    if info.bit_depth != png::BitDepth::Eight {
+        return Err(format!(
+            "icon '{}' uses {}-bit color depth, which is not supported",
+            path, info.bit_depth as u8
+        ));
+    }
+
+    let channels = match info.color_type {
+        png::ColorType::Rgba => 4,
+        png::ColorType::Rgb => 3,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Grayscale => 1,
+        png::ColorType::Indexed => {
+            return Err(format!("icon '{}' uses an indexed palette, which is not supported", path))
+        }
+    };
+
+    let native = Endian::native();
+    let pixel_count = info.width as usize * info.height as usize;
+    let mut argb = Vec::with_capacity(pixel_count * 4);
+    let mut alpha = Vec::with_capacity(pixel_count);
+    for pixel in raw.chunks(channels) {
+        let (r, g, b, a) = match channels {
+            4 => (pixel[0], pixel[1], pixel[2], pixel[3]),
+            3 => (pixel[0], pixel[1], pixel[2], 255),
+            2 => (pixel[0], pixel[0], pixel[0], pixel[1]),
+            _ => (pixel[0], pixel[0], pixel[0], 255),
+        };
+
+        // Premultiply so blending onto the background is a simple `src + dest*(1-a)`
+        let premultiply = |channel: u8| (channel as u16 * a as u16 / 255) as u8;
+        let (r, g, b) = (premultiply(r), premultiply(g), premultiply(b));
+
+        match native {
+            Endian::Big => argb.extend_from_slice(&[a, r, g, b]),
+            Endian::Little => argb.extend_from_slice(&[b, g, r, a]),
+        }
+        alpha.push(a);
+    }
+
+    Ok(Image {
+        width: info.width as usize,
+        height: info.height as usize,
+        argb,
+        alpha,
+    })
+}
+
+impl Image {
+    /// Blits this image into `buffer` (a `canvas_width * canvas_height` ARGB8888
+    /// buffer with no row padding), scaled to `dest_height` while preserving
+    /// aspect ratio, top-left aligned at `(dest_x, dest_y)`. Returns the
+    /// destination width so callers can lay out content after the icon.
+    pub fn blit(
+        &self,
+        buffer: &mut [u8],
+        canvas_width: usize,
+        canvas_height: usize,
+        dest_x: usize,
+        dest_y: usize,
+        dest_height: usize,
+    ) -> usize {
+        if self.width == 0 || self.height == 0 || dest_height == 0 {
+            return 0;
+        }
+
+        let dest_width = self.width * dest_height / self.height;
+
+        for y in 0..dest_height {
+            let dest_row = dest_y + y;
+            if dest_row >= canvas_height {
+                break;
+            }
+            let src_y = y * self.height / dest_height;
+
+            for x in 0..dest_width {
+                let dest_col = dest_x + x;
+                if dest_col >= canvas_width {
+                    break;
+                }
+                let src_x = x * self.width / dest_width;
+
+                let src_pixel = src_y * self.width + src_x;
+                let src_i = src_pixel * 4;
+                let dest_i = (dest_row * canvas_width + dest_col) * 4;
+                let a = self.alpha[src_pixel] as u16;
+
+                if a == 255 {
+                    buffer[dest_i..dest_i + 4].copy_from_slice(&self.argb[src_i..src_i + 4]);
+                } else if a > 0 {
+                    for channel in 0..4 {
+                        let src = self.argb[src_i + channel] as u16;
+                        let dest = buffer[dest_i + channel] as u16;
+                        buffer[dest_i + channel] = (src + dest * (255 - a) / 255) as u8;
+                    }
+                }
+            }
+        }
+
+        dest_width
+    }
+}
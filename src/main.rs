@@ -12,7 +12,7 @@ use smithay_client_toolkit::{
     reexports::{
         calloop,
         client::protocol::{
-            wl_keyboard, wl_output,
+            wl_data_device, wl_data_device_manager, wl_data_source, wl_keyboard, wl_output,
             wl_pointer::{self, ButtonState},
             wl_shm, wl_surface,
         },
@@ -22,7 +22,7 @@ use smithay_client_toolkit::{
     },
     seat::{
         self,
-        keyboard::{map_keyboard_repeat, RepeatKind},
+        keyboard::{self, keysyms, map_keyboard_repeat, RepeatKind},
     },
     shm::DoubleMemPool,
     window::{self, ConceptFrame},
@@ -33,29 +33,50 @@ use std::{
     cell::{Cell, RefCell},
     env,
     io::{self, Read, Seek, SeekFrom, Write},
+    os::unix::io::FromRawFd,
     process::{self, Command},
     rc::Rc,
+    time::Duration,
 };
 
 mod args;
+mod completions;
+mod flags;
+mod help;
+mod image;
+mod suggest;
+mod tokens;
 use args::{Args, WindowType};
 
 const FONT_COLOR: [u8; 4] = [255, 255, 255, 255];
+const BUTTON_COLOR: [u8; 4] = [255, 100, 0, 0];
+const BUTTON_HIGHLIGHT_COLOR: [u8; 4] = [255, 150, 60, 0];
 
 default_environment!(Env,
     fields = [
         layer_shell: SimpleGlobal<zwlr_layer_shell_v1::ZwlrLayerShellV1>,
+        data_device_manager: SimpleGlobal<wl_data_device_manager::WlDataDeviceManager>,
     ],
     singles = [
-        zwlr_layer_shell_v1::ZwlrLayerShellV1 => layer_shell
+        zwlr_layer_shell_v1::ZwlrLayerShellV1 => layer_shell,
+        wl_data_device_manager::WlDataDeviceManager => data_device_manager,
     ],
 );
 
-default_environment!(NormalWindowEnv, desktop);
+default_environment!(NormalWindowEnv, desktop,
+    fields = [
+        data_device_manager: SimpleGlobal<wl_data_device_manager::WlDataDeviceManager>,
+    ],
+    singles = [
+        wl_data_device_manager::WlDataDeviceManager => data_device_manager,
+    ],
+);
 
 #[derive(PartialEq, Copy, Clone)]
 enum RenderEvent {
     Configure { width: u32, height: u32 },
+    /// Redraw at the current dimensions, e.g. because the output scale changed.
+    Redraw,
     Closed,
 }
 
@@ -64,12 +85,31 @@ struct Surface {
     next_render_event: Rc<Cell<Option<RenderEvent>>>,
     pools: DoubleMemPool,
     dimensions: (u32, u32),
+    /// Output scale factor, used to render a HiDPI-sharp buffer
+    scale: Rc<Cell<i32>>,
     /// X, Y coordinates of current cursor position
     pointer_location: Option<(f64, f64)>,
     /// User requested exit
     should_exit: bool,
     click_targets: Vec<ClickTarget>,
+    /// Index into `click_targets` of the keyboard-focused button, if any
+    selected: Option<usize>,
     font_data: Vec<u8>,
+    icon: Option<image::Image>,
+    clipboard: Option<Clipboard>,
+    /// Serial from the most recent input event (keyboard enter or pointer button press),
+    /// required to claim the selection
+    input_serial: Cell<u32>,
+    /// Kept alive only so the compositor can still ask it for the clipboard contents;
+    /// replaced (dropping the old one) whenever a new copy is requested.
+    clipboard_offer: Option<wl_data_source::WlDataSource>,
+}
+
+/// A seat's data device, used to offer the clipboard selection
+#[derive(Clone)]
+struct Clipboard {
+    manager: wl_data_device_manager::WlDataDeviceManager,
+    device: wl_data_device::WlDataDevice,
 }
 
 struct ClickTarget {
@@ -84,6 +124,8 @@ enum ClickHandler {
     Exit,
     /// Run command
     RunCommand(String),
+    /// Copy the given text to the clipboard
+    CopyToClipboard(String),
 }
 
 impl Surface {
@@ -91,6 +133,8 @@ impl Surface {
         args: Args,
         pools: DoubleMemPool,
         next_render_event: Rc<Cell<Option<RenderEvent>>>,
+        scale: Rc<Cell<i32>>,
+        clipboard: Option<Clipboard>,
     ) -> Self {
         let mut font_data = Vec::new();
         std::fs::File::open(
@@ -106,15 +150,31 @@ impl Surface {
         .read_to_end(&mut font_data)
         .unwrap();
 
+        // TODO imply a default icon from `args.message_type` (warn vs error) once
+        // this crate bundles icon assets to fall back on.
+        let icon = args.icon.as_deref().and_then(|path| match image::load(path) {
+            Ok(icon) => Some(icon),
+            Err(e) => {
+                eprintln!("WARN: {}", e);
+                None
+            }
+        });
+
         Self {
             args,
             next_render_event,
             pools,
             dimensions: (0, 0),
+            scale,
             pointer_location: None,
             should_exit: false,
             click_targets: vec![],
+            selected: None,
             font_data,
+            icon,
+            clipboard,
+            input_serial: Cell::new(0),
+            clipboard_offer: None,
         }
     }
 
@@ -128,10 +188,18 @@ impl Surface {
                 self.draw(surface);
                 false
             }
+            Some(RenderEvent::Redraw) => {
+                self.draw(surface);
+                false
+            }
             None => self.should_exit,
         }
     }
 
+    fn clear_pointer_location(&mut self) {
+        self.pointer_location = None;
+    }
+
     fn handle_pointer_event(&mut self, event: &wl_pointer::Event) {
         match event {
             wl_pointer::Event::Enter {
@@ -145,46 +213,150 @@ impl Surface {
                 ..
             } => self.pointer_location = Some((*surface_x, *surface_y)),
             wl_pointer::Event::Button {
+                serial,
                 state: ButtonState::Pressed,
                 ..
             } => {
+                self.input_serial.set(*serial);
+
                 let mut matching_click_handler = None;
+                let scale = self.scale.get().max(1) as f64;
                 for click_target in &self.click_targets {
-                    if let Some(click_position) = self.pointer_location {
+                    if let Some((x, y)) = self.pointer_location {
+                        // Pointer coordinates arrive in surface-local (unscaled) units,
+                        // but click targets are stored in the scaled buffer space.
+                        let click_position = (x * scale, y * scale);
                         if let Some(handler) = click_target.process_click(click_position) {
                             matching_click_handler = Some(handler);
                         }
                     }
                 }
 
-                match matching_click_handler {
-                    Some(ClickHandler::Exit) => self.should_exit = true,
-                    Some(ClickHandler::RunCommand(cmd)) => {
-                        match Command::new("/bin/sh").arg("-c").arg(cmd).spawn() {
-                            Ok(_) => (),
-                            Err(e) => eprintln!("{:?}", e),
-                        }
-                    }
-                    None => {}
+                if let Some(handler) = matching_click_handler {
+                    self.run_handler(handler);
                 }
             }
             _ => {}
         }
     }
 
+    /// Handles a keyboard event, redrawing `surface` if the selection changed.
+    fn handle_keyboard_event(&mut self, event: &keyboard::Event, surface: &wl_surface::WlSurface) {
+        if let keyboard::Event::Enter { serial, .. } = event {
+            self.input_serial.set(*serial);
+        }
+
+        let (keysym, state) = match event {
+            keyboard::Event::Key { keysym, state, .. } => (*keysym, *state),
+            _ => return,
+        };
+
+        if state != wl_keyboard::KeyState::Pressed {
+            return;
+        }
+
+        let mut needs_redraw = false;
+        match keysym {
+            keysyms::XKB_KEY_Escape => self.should_exit = true,
+            keysyms::XKB_KEY_Return | keysyms::XKB_KEY_KP_Enter => {
+                if let Some(index) = self.selected {
+                    self.activate(index);
+                }
+            }
+            keysyms::XKB_KEY_Tab | keysyms::XKB_KEY_Right => {
+                self.advance_selection(1);
+                needs_redraw = true;
+            }
+            keysyms::XKB_KEY_ISO_Left_Tab | keysyms::XKB_KEY_Left => {
+                self.advance_selection(-1);
+                needs_redraw = true;
+            }
+            keysyms::XKB_KEY_1..=keysyms::XKB_KEY_9 => {
+                self.activate((keysym - keysyms::XKB_KEY_1) as usize);
+            }
+            _ => {}
+        }
+
+        if needs_redraw {
+            self.draw(surface);
+        }
+    }
+
+    /// Moves `selected` forward (positive `delta`) or backward, wrapping around
+    /// the available click targets.
+    fn advance_selection(&mut self, delta: isize) {
+        self.selected = advance_selection_index(self.selected, delta, self.click_targets.len());
+    }
+
+    /// Runs the handler for the click target at `index`, if one exists.
+    fn activate(&mut self, index: usize) {
+        if let Some(handler) = self.click_targets.get(index).map(|t| t.handler.clone()) {
+            self.run_handler(handler);
+        }
+    }
+
+    fn run_handler(&mut self, handler: ClickHandler) {
+        match handler {
+            ClickHandler::Exit => self.should_exit = true,
+            ClickHandler::RunCommand(cmd) => {
+                match Command::new("/bin/sh").arg("-c").arg(cmd).spawn() {
+                    Ok(_) => (),
+                    Err(e) => eprintln!("{:?}", e),
+                }
+            }
+            ClickHandler::CopyToClipboard(text) => self.offer_clipboard(text),
+        }
+    }
+
+    /// Claims the clipboard selection and offers `text` as its `text/plain` contents.
+    fn offer_clipboard(&mut self, text: String) {
+        let clipboard = match &self.clipboard {
+            Some(clipboard) => clipboard,
+            None => {
+                eprintln!("WARN: no data device available, cannot copy to clipboard");
+                return;
+            }
+        };
+
+        let mime_type = "text/plain;charset=utf-8".to_string();
+        let source = clipboard.manager.create_data_source();
+        source.quick_assign(move |_, event, _| {
+            if let wl_data_source::Event::Send { mime_type, fd } = event {
+                if mime_type == "text/plain;charset=utf-8" {
+                    let mut file = unsafe { std::fs::File::from_raw_fd(fd) };
+                    if let Err(e) = file.write_all(text.as_bytes()) {
+                        eprintln!("WARN: failed to write clipboard contents: {}", e);
+                    }
+                }
+            }
+        });
+        source.offer(mime_type);
+
+        let source = source.detach();
+        clipboard
+            .device
+            .set_selection(Some(&source), self.input_serial.get());
+
+        // The source must outlive this call or the compositor can't ask it for data later.
+        self.clipboard_offer = Some(source);
+    }
+
     fn draw(&mut self, surface: &wl_surface::WlSurface) {
         let pool = match self.pools.pool() {
             Some(pool) => pool,
             None => return,
         };
 
-        let stride = 4 * self.dimensions.0 as i32;
-        let width = self.dimensions.0 as i32;
-        let height = self.dimensions.1 as i32;
+        self.click_targets.clear();
+
+        let scale = self.scale.get().max(1);
+        let width = self.dimensions.0 as i32 * scale;
+        let height = self.dimensions.1 as i32 * scale;
+        let stride = 4 * width;
 
-        let vertical_padding = 2;
-        let horizontal_padding = 10;
-        let max_text_size = 16.;
+        let vertical_padding = 2 * scale as usize;
+        let horizontal_padding = 10 * scale as usize;
+        let max_text_size = 16. * scale as f32;
         let text_h = {
             let h = height as f32 / 2.;
             if h > max_text_size {
@@ -217,33 +389,65 @@ impl Surface {
         );
         canvas.draw(&block);
 
+        // Draw icon, if any, left-aligned and scaled to fit the message row
+        let icon_width = match &self.icon {
+            Some(icon) => {
+                let icon_height = height as usize - 2 * vertical_padding;
+                icon.blit(
+                    canvas.buffer,
+                    width as usize,
+                    height as usize,
+                    horizontal_padding,
+                    vertical_padding,
+                    icon_height,
+                )
+            }
+            None => 0,
+        };
+        let message_x = if icon_width > 0 {
+            horizontal_padding + icon_width + horizontal_padding
+        } else {
+            horizontal_padding
+        };
+
         // Draw buttons
         let mut right_most_pixel = width as usize;
 
-        let mut draw_button = move |text: String, font_data: &[u8], canvas: &mut Canvas| {
-            let mut text = text::Text::new((0, 0), FONT_COLOR, font_data, text_h, 1.0, text);
-            let text_width = text.get_width();
-            let button_width = text_width + 2 * horizontal_padding;
-            let block_height = height as usize - vertical_padding * 2;
-            let block_pos = (
-                right_most_pixel as usize - button_width - horizontal_padding,
-                vertical_padding,
-            );
-            let text_pos = (
-                block_pos.0 + horizontal_padding,
-                ((block_height as f32 - text_h) / 2.) as usize,
-            );
-            text.pos = text_pos;
-            let size = (button_width as usize, block_height as usize);
-            let block = rectangle::Rectangle::new(block_pos, size, None, Some([255, 100, 0, 0]));
-            canvas.draw(&block);
-            canvas.draw(&text);
-
-            right_most_pixel = block_pos.0;
-            (block_pos, size)
-        };
+        let mut draw_button =
+            move |text: String, font_data: &[u8], canvas: &mut Canvas, highlighted: bool| {
+                let mut text = text::Text::new((0, 0), FONT_COLOR, font_data, text_h, 1.0, text);
+                let text_width = text.get_width();
+                let button_width = text_width + 2 * horizontal_padding;
+                let block_height = height as usize - vertical_padding * 2;
+                let block_pos = (
+                    right_most_pixel as usize - button_width - horizontal_padding,
+                    vertical_padding,
+                );
+                let text_pos = (
+                    block_pos.0 + horizontal_padding,
+                    ((block_height as f32 - text_h) / 2.) as usize,
+                );
+                text.pos = text_pos;
+                let size = (button_width as usize, block_height as usize);
+                let color = if highlighted {
+                    BUTTON_HIGHLIGHT_COLOR
+                } else {
+                    BUTTON_COLOR
+                };
+                let block = rectangle::Rectangle::new(block_pos, size, None, Some(color));
+                canvas.draw(&block);
+                canvas.draw(&text);
+
+                right_most_pixel = block_pos.0;
+                (block_pos, size)
+            };
 
-        let (position, size) = draw_button("x".into(), &self.font_data, &mut canvas);
+        let (position, size) = draw_button(
+            "x".into(),
+            &self.font_data,
+            &mut canvas,
+            self.selected == Some(0),
+        );
         let click_target = ClickTarget {
             position,
             size,
@@ -251,8 +455,28 @@ impl Surface {
         };
         self.click_targets.push(click_target);
 
+        if let Some(copy_button_text) = self.args.copy_button.clone() {
+            let (position, size) = draw_button(
+                copy_button_text,
+                &self.font_data,
+                &mut canvas,
+                self.selected == Some(self.click_targets.len()),
+            );
+            let click_target = ClickTarget {
+                position,
+                size,
+                handler: ClickHandler::CopyToClipboard(self.args.message.clone()),
+            };
+            self.click_targets.push(click_target);
+        }
+
         for button in self.args.buttons.iter().cloned() {
-            let (position, size) = draw_button(button.text, &self.font_data, &mut canvas);
+            let (position, size) = draw_button(
+                button.text,
+                &self.font_data,
+                &mut canvas,
+                self.selected == Some(self.click_targets.len()),
+            );
             let click_target = ClickTarget {
                 position,
                 size,
@@ -263,7 +487,7 @@ impl Surface {
 
         // Draw message
         let text = text::Text::new(
-            (horizontal_padding, height as usize / 2 - text_hh as usize),
+            (message_x, height as usize / 2 - text_hh as usize),
             FONT_COLOR,
             &self.font_data,
             text_h,
@@ -279,6 +503,9 @@ impl Surface {
         // Create a new buffer from the pool
         let buffer = pool.buffer(0, width, height, stride, wl_shm::Format::Argb8888);
 
+        // Tell the compositor this buffer is rendered at `scale`x so it isn't upscaled
+        surface.set_buffer_scale(scale);
+
         // Attach the buffer to the surface and mark the entire surface as damaged
         surface.attach(Some(&buffer), 0, 0);
         surface.damage_buffer(0, 0, width as i32, height as i32);
@@ -288,6 +515,20 @@ impl Surface {
     }
 }
 
+/// Computes the next selected index, wrapping around `len` targets. `current`
+/// is `None` when nothing is selected yet: stepping forward (`delta > 0`) lands
+/// on the first target, stepping backward lands on the last.
+fn advance_selection_index(current: Option<usize>, delta: isize, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    let len = len as isize;
+    let current = current.map_or(if delta < 0 { len } else { -1 }, |index| index as isize);
+    let next = (current + delta).rem_euclid(len);
+    Some(next as usize)
+}
+
 impl ClickTarget {
     fn process_click(&self, click_position: (f64, f64)) -> Option<ClickHandler> {
         let (click_x, click_y) = click_position;
@@ -308,7 +549,8 @@ impl ClickTarget {
 
 fn main() {
     let mut args = match args::parse(env::args()) {
-        Ok(args) => args,
+        Ok(args::Parsed::Args(args)) => args,
+        Ok(args::Parsed::Handled) => process::exit(0),
         Err(message) => {
             eprintln!("{}", message);
 
@@ -331,16 +573,32 @@ fn main() {
 
     // TODO make this a match statement
     if args.window_type == WindowType::Bar {
-        let (env, display, queue) =
-            init_default_environment!(Env, fields = [layer_shell: SimpleGlobal::new(),])
-                .expect("Initial roundtrip failed!");
+        let (env, display, queue) = init_default_environment!(
+            Env,
+            fields = [
+                layer_shell: SimpleGlobal::new(),
+                data_device_manager: SimpleGlobal::new(),
+            ]
+        )
+        .expect("Initial roundtrip failed!");
 
         let surfaces = Rc::new(RefCell::new(Vec::new()));
+        let timeout = args.timeout;
 
         let layer_shell = env.require_global::<zwlr_layer_shell_v1::ZwlrLayerShellV1>();
 
+        // Only the first seat is offered clipboard access; waysay isn't meant for
+        // multi-seat setups.
+        let data_device_manager =
+            env.require_global::<wl_data_device_manager::WlDataDeviceManager>();
+        let clipboard = env.get_all_seats().into_iter().next().map(|seat| Clipboard {
+            manager: data_device_manager.detach(),
+            device: data_device_manager.get_data_device(&seat).detach(),
+        });
+
         let env_handle = env.clone();
         let surfaces_handle = Rc::clone(&surfaces);
+        let clipboard_handle = clipboard.clone();
         let output_handler = move |output: wl_output::WlOutput, info: &OutputInfo| {
             if info.obsolete {
                 // an output has been removed, release it
@@ -350,7 +608,29 @@ fn main() {
                 output.release();
             } else {
                 // an output has been created, construct a surface for it
-                let surface = env_handle.create_surface().detach();
+
+                // `OutputInfo::scale_factor` is known synchronously, so seed from it
+                // rather than waiting on the async scale callback below, which only
+                // fires once the surface is mapped (i.e. after the first `draw()`).
+                let surface_scale = Rc::new(Cell::new(info.scale_factor));
+                let surface_scale_handle = Rc::clone(&surface_scale);
+
+                let next_render_event = Rc::new(Cell::new(None::<RenderEvent>));
+                let next_render_event_handle = Rc::clone(&next_render_event);
+                let next_render_event_handle_for_scale = Rc::clone(&next_render_event);
+                let surface = env_handle
+                    .create_surface_with_scale_callback(move |_surface, scale, _| {
+                        surface_scale_handle.set(scale);
+
+                        // Trigger a redraw at the (possibly new) scale, without clobbering
+                        // a pending close or a configure that's still carrying fresh
+                        // dimensions.
+                        match next_render_event_handle_for_scale.get() {
+                            Some(RenderEvent::Closed) | Some(RenderEvent::Configure { .. }) => {}
+                            _ => next_render_event_handle_for_scale.set(Some(RenderEvent::Redraw)),
+                        }
+                    })
+                    .detach();
                 let pools = env_handle
                     .create_double_pool(|_| {})
                     .expect("Failed to create a memory pool!");
@@ -369,9 +649,9 @@ fn main() {
                         | zwlr_layer_surface_v1::Anchor::Right,
                 );
                 layer_surface.set_exclusive_zone(height as i32);
+                layer_surface
+                    .set_keyboard_interactivity(zwlr_layer_surface_v1::KeyboardInteractivity::Exclusive);
 
-                let next_render_event = Rc::new(Cell::new(None::<RenderEvent>));
-                let next_render_event_handle = Rc::clone(&next_render_event);
                 layer_surface.quick_assign(move |layer_surface, event, _| {
                     match (event, next_render_event_handle.get()) {
                         (zwlr_layer_surface_v1::Event::Closed, _) => {
@@ -396,12 +676,39 @@ fn main() {
 
                 (*surfaces_handle.borrow_mut()).push((
                     info.id,
-                    Surface::new(args.clone(), pools, next_render_event),
+                    Surface::new(
+                        args.clone(),
+                        pools,
+                        next_render_event,
+                        surface_scale,
+                        clipboard_handle.clone(),
+                    ),
                     surface,
                 ));
             }
         };
 
+        let mut event_loop = calloop::EventLoop::<()>::new().unwrap();
+        // Keep mapped keyboards alive for as long as the event loop runs.
+        let mut keyboards = Vec::new();
+
+        if let Some(timeout) = timeout {
+            let surfaces_handle = Rc::clone(&surfaces);
+            // The timer is backed by a timerfd, so its expiry also wakes the blocking
+            // `dispatch(None, ...)` call below, same as any other registered event source.
+            let timer_source = calloop::timer::Timer::new().expect("Failed to create timer");
+            let timer_handle = timer_source.handle();
+            event_loop
+                .handle()
+                .insert_source(timer_source, move |_event, _metadata, _shared_data| {
+                    for (_, surface, _) in surfaces_handle.borrow_mut().iter_mut() {
+                        surface.should_exit = true;
+                    }
+                })
+                .expect("Failed to insert timeout timer into event loop");
+            timer_handle.add_timeout(Duration::from_secs(timeout), ());
+        }
+
         for seat in env.get_all_seats() {
             if let Some(has_ptr) = seat::with_seat_data(&seat, |seat_data| {
                 seat_data.has_pointer && !seat_data.defunct
@@ -410,15 +717,74 @@ fn main() {
                     let pointer = seat.get_pointer();
                     // let surface = window.surface().clone();
                     let surfaces_handle = surfaces.clone();
+                    let mut focused_surface: Option<wl_surface::WlSurface> = None;
                     pointer.quick_assign(move |_, event, _| {
-                        for surface in (*surfaces_handle).borrow_mut().iter_mut() {
-                            // We should be filtering this down so we only pass
-                            // the event on to the appropriate surface. TODO
-                            surface.1.handle_pointer_event(&event);
+                        if let wl_pointer::Event::Leave { surface, .. } = &event {
+                            if focused_surface.as_ref() == Some(surface) {
+                                focused_surface = None;
+                            }
+                            for (_, s, wl_surface) in (*surfaces_handle).borrow_mut().iter_mut() {
+                                if wl_surface == surface {
+                                    s.clear_pointer_location();
+                                }
+                            }
+                            return;
+                        }
+
+                        if let wl_pointer::Event::Enter { surface, .. } = &event {
+                            focused_surface = Some(surface.clone());
+                        }
+
+                        if let Some(focused) = &focused_surface {
+                            for (_, s, wl_surface) in (*surfaces_handle).borrow_mut().iter_mut() {
+                                if wl_surface == focused {
+                                    s.handle_pointer_event(&event);
+                                }
+                            }
                         }
                     });
                 }
             }
+
+            if let Some(has_kbd) = seat::with_seat_data(&seat, |seat_data| {
+                seat_data.has_keyboard && !seat_data.defunct
+            }) {
+                if has_kbd {
+                    let surfaces_handle = surfaces.clone();
+                    let mut focused_surface: Option<wl_surface::WlSurface> = None;
+                    match map_keyboard_repeat(
+                        event_loop.handle(),
+                        &seat,
+                        None,
+                        RepeatKind::System,
+                        move |event, _, _| {
+                            if let keyboard::Event::Leave { surface, .. } = &event {
+                                if focused_surface.as_ref() == Some(surface) {
+                                    focused_surface = None;
+                                }
+                                return;
+                            }
+
+                            if let keyboard::Event::Enter { surface, .. } = &event {
+                                focused_surface = Some(surface.clone());
+                            }
+
+                            if let Some(focused) = &focused_surface {
+                                for (_, surface, wl_surface) in
+                                    (*surfaces_handle).borrow_mut().iter_mut()
+                                {
+                                    if wl_surface == focused {
+                                        surface.handle_keyboard_event(&event, wl_surface);
+                                    }
+                                }
+                            }
+                        },
+                    ) {
+                        Ok(kbd) => keyboards.push(kbd),
+                        Err(e) => eprintln!("Failed to map keyboard on seat: {:?}", e),
+                    }
+                }
+            }
         }
 
         // Process currently existing outputs
@@ -433,8 +799,6 @@ fn main() {
         let _listner_handle =
             env.listen_for_outputs(move |output, info, _| output_handler(output, info));
 
-        let mut event_loop = calloop::EventLoop::<()>::new().unwrap();
-
         WaylandSource::new(queue)
             .quick_insert(event_loop.handle())
             .unwrap();
@@ -465,8 +829,12 @@ fn main() {
             event_loop.dispatch(None, &mut ()).unwrap();
         }
     } else {
-        let (env, display, queue) =
-            init_default_environment!(NormalWindowEnv, desktop).expect("Initial roundtrip failed!");
+        let (env, display, queue) = init_default_environment!(
+            NormalWindowEnv,
+            desktop,
+            fields = [data_device_manager: SimpleGlobal::new(),]
+        )
+        .expect("Initial roundtrip failed!");
         /*
          * Prepare a calloop event loop to handle key repetion
          */
@@ -484,7 +852,17 @@ fn main() {
          * Init wayland objects
          */
 
+        // Only the first seat is offered clipboard access; waysay isn't meant for
+        // multi-seat setups.
+        let data_device_manager =
+            env.require_global::<wl_data_device_manager::WlDataDeviceManager>();
+        let clipboard = env.get_all_seats().into_iter().next().map(|seat| Clipboard {
+            manager: data_device_manager.detach(),
+            device: data_device_manager.get_data_device(&seat).detach(),
+        });
+
         let surface = env.create_surface().detach();
+        let surface_handle = surface.clone();
 
         let mut window = env
             .create_window::<ConceptFrame, _>(surface, dimensions, move |evt, mut dispatch_data| {
@@ -510,6 +888,32 @@ fn main() {
             .create_double_pool(|_| {})
             .expect("Failed to create a memory pool !");
 
+        let timeout = args.timeout;
+        let next_render_event = Rc::new(Cell::new(None::<RenderEvent>));
+        let my_surface = Rc::new(RefCell::new(Surface::new(
+            args,
+            pools,
+            Rc::clone(&next_render_event),
+            Rc::new(Cell::new(1)),
+            clipboard,
+        )));
+        my_surface.borrow_mut().dimensions = dimensions;
+
+        if let Some(timeout) = timeout {
+            let my_surface = Rc::clone(&my_surface);
+            // The timer is backed by a timerfd, so its expiry also wakes the blocking
+            // `dispatch(None, ...)` call below, same as any other registered event source.
+            let timer_source = calloop::timer::Timer::new().expect("Failed to create timer");
+            let timer_handle = timer_source.handle();
+            event_loop
+                .handle()
+                .insert_source(timer_source, move |_event, _metadata, _shared_data| {
+                    my_surface.borrow_mut().should_exit = true;
+                })
+                .expect("Failed to insert timeout timer into event loop");
+            timer_handle.add_timeout(Duration::from_secs(timeout), ());
+        }
+
         /*
          * Keyboard initialization
          */
@@ -529,13 +933,18 @@ fn main() {
             }) {
                 if has_kbd {
                     let _seat_name = name.clone();
+                    let my_surface = Rc::clone(&my_surface);
+                    let surface_handle = surface_handle.clone();
                     match map_keyboard_repeat(
                         event_loop.handle(),
                         &seat,
                         None,
                         RepeatKind::System,
-                        // TODO handle key here
-                        move |_event, _, _| {},
+                        move |event, _, _| {
+                            my_surface
+                                .borrow_mut()
+                                .handle_keyboard_event(&event, &surface_handle);
+                        },
                     ) {
                         Ok((kbd, repeat_source)) => {
                             seats.push((name, Some((kbd, repeat_source))));
@@ -553,6 +962,8 @@ fn main() {
 
         // then setup a listener for changes
         let loop_handle = event_loop.handle();
+        let my_surface_handle = Rc::clone(&my_surface);
+        let surface_handle_for_seats = surface_handle.clone();
         let _seat_listener = env.listen_for_seats(move |seat, seat_data, _| {
             // find the seat in the vec of seats, or insert it if it is unknown
             let idx = seats.iter().position(|(name, _)| name == &seat_data.name);
@@ -567,12 +978,18 @@ fn main() {
                 if opt_kbd.is_none() {
                     // we should initalize a keyboard
                     let _seat_name = seat_data.name.clone();
+                    let my_surface = Rc::clone(&my_surface_handle);
+                    let surface_handle = surface_handle_for_seats.clone();
                     match map_keyboard_repeat(
                         loop_handle.clone(),
                         &seat,
                         None,
                         RepeatKind::System,
-                        move |_event, _, _| {}, // TODO handle key
+                        move |event, _, _| {
+                            my_surface
+                                .borrow_mut()
+                                .handle_keyboard_event(&event, &surface_handle);
+                        },
                     ) {
                         Ok((kbd, repeat_source)) => {
                             *opt_kbd = Some((kbd, repeat_source));
@@ -590,13 +1007,6 @@ fn main() {
             }
         });
 
-        let next_render_event = Rc::new(Cell::new(None::<RenderEvent>));
-        let _next_render_event_handle = Rc::clone(&next_render_event);
-        let mut my_surface = Surface::new(args, pools, next_render_event);
-        // TODO does this make sense?
-        // configure default dimensions to those chosen when creating the window
-        my_surface.dimensions = dimensions;
-
         let mut next_action = None;
 
         WaylandSource::new(queue)
@@ -616,14 +1026,20 @@ fn main() {
                 }) => {
                     if let Some((w, h)) = new_size {
                         window.resize(w, h);
-                        my_surface.dimensions = (w, h);
+                        my_surface.borrow_mut().dimensions = (w, h);
                     }
-                    my_surface.draw(window.surface());
+                    my_surface.borrow_mut().draw(window.surface());
                     window.refresh();
                 }
                 None => {}
             }
 
+            // The timer, the "x" button, and Escape all set this instead of posting a
+            // window::Event, since none of them come from the compositor.
+            if my_surface.borrow().should_exit {
+                break;
+            }
+
             // always flush the connection before going to sleep waiting for events
             display.flush().unwrap();
 
@@ -631,3 +1047,33 @@ fn main() {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::advance_selection_index;
+
+    #[test]
+    fn no_targets() {
+        assert_eq!(None, advance_selection_index(None, 1, 0));
+    }
+
+    #[test]
+    fn forward_from_no_selection_lands_on_first() {
+        assert_eq!(Some(0), advance_selection_index(None, 1, 3));
+    }
+
+    #[test]
+    fn backward_from_no_selection_lands_on_last() {
+        assert_eq!(Some(2), advance_selection_index(None, -1, 3));
+    }
+
+    #[test]
+    fn forward_wraps_to_start() {
+        assert_eq!(Some(0), advance_selection_index(Some(2), 1, 3));
+    }
+
+    #[test]
+    fn backward_wraps_to_end() {
+        assert_eq!(Some(2), advance_selection_index(Some(0), -1, 3));
+    }
+}
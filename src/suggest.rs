@@ -0,0 +1,65 @@
+//! "did you mean" suggestions for unrecognized flags, based on Levenshtein edit
+//! distance against the known long flags.
+
+use crate::flags;
+
+/// Returns the closest known long flag to `token`, if one is close enough to be
+/// worth suggesting.
+pub fn closest_flag(token: &str) -> Option<&'static str> {
+    let threshold = (token.chars().count() / 3).max(2);
+
+    flags::long_flags()
+        .map(|candidate| (candidate, edit_distance(token, candidate)))
+        .min_by_key(|(_, distance)| *distance)
+        .filter(|(_, distance)| *distance <= threshold)
+        .map(|(candidate, _)| candidate)
+}
+
+/// Levenshtein edit distance between `a` and `b`, computed with a single rolling
+/// row of length `b.len() + 1`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous_diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            let substitution = previous_diagonal + cost;
+            let deletion = row[j] + 1;
+            let insertion = row[j + 1] + 1;
+
+            previous_diagonal = row[j + 1];
+            row[j + 1] = substitution.min(deletion).min(insertion);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest_flag, edit_distance};
+
+    #[test]
+    fn identical_strings_have_no_distance() {
+        assert_eq!(0, edit_distance("--message", "--message"));
+    }
+
+    #[test]
+    fn single_substitution() {
+        assert_eq!(1, edit_distance("--mussage", "--message"));
+    }
+
+    #[test]
+    fn suggests_the_closest_typo() {
+        assert_eq!(Some("--message"), closest_flag("--mesage"));
+    }
+
+    #[test]
+    fn does_not_suggest_unrelated_flags() {
+        assert_eq!(None, closest_flag("--xyzzy"));
+    }
+}
@@ -0,0 +1,72 @@
+//! Splits raw `env::args()` strings into flag tokens, honoring the GNU conventions
+//! `args::parse` needs: `--long=value`, bundled short flags (`-mhello`), and a `--`
+//! terminator after which everything is literal text.
+
+pub enum Token {
+    /// A flag's alias (e.g. `-m` or `--message`), with any value attached via `=`
+    /// or bundling (e.g. the `hello` in `-mhello`).
+    Flag {
+        alias: String,
+        attached: Option<String>,
+    },
+    /// A raw token seen after a `--` terminator.
+    Literal(String),
+}
+
+pub struct Tokenizer<I> {
+    inner: I,
+    literal: bool,
+}
+
+impl<I: Iterator<Item = String>> Tokenizer<I> {
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            literal: false,
+        }
+    }
+
+    pub fn next_token(&mut self) -> Option<Token> {
+        loop {
+            let raw = self.inner.next()?;
+
+            if self.literal {
+                return Some(Token::Literal(raw));
+            }
+
+            if raw == "--" {
+                self.literal = true;
+                continue;
+            }
+
+            let (alias, attached) = split_attached_value(&raw);
+            return Some(Token::Flag {
+                alias: alias.to_string(),
+                attached: attached.map(str::to_string),
+            });
+        }
+    }
+
+    /// Reads the value for a flag that takes one: the value attached to the flag's
+    /// own token if there was one, otherwise the next raw token untouched by the
+    /// `=`/bundling rules (so a value is never itself split or treated as `--`).
+    pub fn value(&mut self, attached: Option<String>) -> Option<String> {
+        attached.or_else(|| self.inner.next())
+    }
+}
+
+/// Splits `--long=value` and `-m=value` on their first `=`, and bundled short flags
+/// like `-mhello` on their second character, into `(alias, attached_value)`.
+fn split_attached_value(raw: &str) -> (&str, Option<&str>) {
+    if let Some(eq) = raw.find('=') {
+        return (&raw[..eq], Some(&raw[eq + 1..]));
+    }
+
+    if raw.starts_with('-') && !raw.starts_with("--") {
+        if let Some((split, _)) = raw.char_indices().nth(2) {
+            return (&raw[..split], Some(&raw[split..]));
+        }
+    }
+
+    (raw, None)
+}